@@ -10,16 +10,19 @@
 
 use std::io;
 use std::mem;
-use std::ptr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use windows_sys::Win32::Foundation::FILETIME;
 use windows_sys::Win32::Foundation::SYSTEMTIME;
 use windows_sys::Win32::System::Time::FileTimeToSystemTime;
+use windows_sys::Win32::System::Time::GetDynamicTimeZoneInformation;
 use windows_sys::Win32::System::Time::GetTimeZoneInformation;
+use windows_sys::Win32::System::Time::GetTimeZoneInformationForYear;
 use windows_sys::Win32::System::Time::SystemTimeToFileTime;
-use windows_sys::Win32::System::Time::SystemTimeToTzSpecificLocalTime;
-use windows_sys::Win32::System::Time::TzSpecificLocalTimeToSystemTime;
+use windows_sys::Win32::System::Time::SystemTimeToTzSpecificLocalTimeEx;
+use windows_sys::Win32::System::Time::TzSpecificLocalTimeToSystemTimeEx;
+use windows_sys::Win32::System::Time::DYNAMIC_TIME_ZONE_INFORMATION;
+use windows_sys::Win32::System::Time::TIME_ZONE_INFORMATION;
 
 use super::{FixedOffset, Local};
 use crate::{DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
@@ -47,13 +50,51 @@ pub(super) fn naive_to_local(d: &NaiveDateTime, local: bool) -> LocalResult<Date
         tm_nsec: 0,
     };
 
-    let spec = Timespec {
-        sec: match local {
-            false => utc_tm_to_time(&tm),
-            true => local_tm_to_time(&tm),
-        },
-        nsec: tm.tm_nsec,
+    if !local {
+        let spec = Timespec { sec: utc_tm_to_time(&tm), nsec: tm.tm_nsec };
+
+        // Adjust for leap seconds
+        let mut tm = spec.local();
+        assert_eq!(tm.tm_nsec, 0);
+        tm.tm_nsec = d.nanosecond() as i32;
+
+        return tm_to_datetime(tm);
+    }
+
+    // Resolving a wall-clock time can yield zero, one, or two instants: around a
+    // DST transition the requested time may fall in a spring-forward gap (and so
+    // not exist) or a fall-back overlap (and so be ambiguous).
+    let sec = local_tm_to_time(&tm);
+
+    // Round-trip the chosen instant back to broken-down local time. If the
+    // fields differ from the request the wall time falls in a gap: it does not
+    // exist, so there is no instant to return.
+    if !maps_back_to(&tm, sec) {
+        return LocalResult::None;
+    }
+
+    // Probe the standard and daylight offsets for this wall time. If both map
+    // back to the requested time the instant is ambiguous.
+    let wall = utc_tm_to_time(&tm);
+    let (std_off, dst_off) = unsafe {
+        let tz = dynamic_tz_for_year((tm.tm_year + 1900) as u16);
+        (-60 * (tz.Bias + tz.StandardBias), -60 * (tz.Bias + tz.DaylightBias))
     };
+    if std_off != dst_off
+        && maps_back_to(&tm, wall - i64::from(std_off))
+        && maps_back_to(&tm, wall - i64::from(dst_off))
+    {
+        // The earlier of the two instants is the one with the larger (daylight)
+        // offset east of UTC.
+        let (earliest, latest) = if dst_off > std_off {
+            (datetime_at_offset(d, dst_off), datetime_at_offset(d, std_off))
+        } else {
+            (datetime_at_offset(d, std_off), datetime_at_offset(d, dst_off))
+        };
+        return LocalResult::Ambiguous(earliest, latest);
+    }
+
+    let spec = Timespec { sec, nsec: tm.tm_nsec };
 
     // Adjust for leap seconds
     let mut tm = spec.local();
@@ -63,6 +104,25 @@ pub(super) fn naive_to_local(d: &NaiveDateTime, local: bool) -> LocalResult<Date
     tm_to_datetime(tm)
 }
 
+/// Converts `sec` back into broken-down local time and reports whether the
+/// resulting wall-clock fields match those requested in `tm`.
+fn maps_back_to(tm: &Tm, sec: i64) -> bool {
+    let rt = Timespec { sec, nsec: 0 }.local();
+    rt.tm_sec == tm.tm_sec
+        && rt.tm_min == tm.tm_min
+        && rt.tm_hour == tm.tm_hour
+        && rt.tm_mday == tm.tm_mday
+        && rt.tm_mon == tm.tm_mon
+        && rt.tm_year == tm.tm_year
+}
+
+/// Builds the `DateTime<Local>` for the requested wall time `d` at a given UTC
+/// offset, in seconds east of UTC.
+fn datetime_at_offset(d: &NaiveDateTime, utcoff: i32) -> DateTime<Local> {
+    let offset = FixedOffset::east_opt(utcoff).unwrap();
+    DateTime::from_utc(*d - offset, offset)
+}
+
 /// Converts a `time::Tm` struct into the timezone-aware `DateTime`.
 fn tm_to_datetime(mut tm: Tm) -> LocalResult<DateTime<Local>> {
     if tm.tm_sec >= 60 {
@@ -84,7 +144,6 @@ fn tm_to_datetime(mut tm: Tm) -> LocalResult<DateTime<Local>> {
         Some(time) => {
             let offset = FixedOffset::east_opt(tm.tm_utcoff).unwrap();
             let datetime = DateTime::from_utc(date.and_time(time) - offset, offset);
-            // #TODO - there should be ambiguous cases, investigate?
             LocalResult::Single(datetime)
         }
         None => LocalResult::None,
@@ -254,22 +313,47 @@ fn time_to_local_tm(sec: i64, tm: &mut Tm) {
         let mut utc = mem::zeroed();
         let mut local = mem::zeroed();
         call!(FileTimeToSystemTime(&ft, &mut utc));
-        call!(SystemTimeToTzSpecificLocalTime(ptr::null(), &utc, &mut local));
+
+        // Use the DST rules that applied during the year of the timestamp rather
+        // than today's rules, so offsets for historical dates are correct.
+        let tz = dynamic_tz_for_year(utc.wYear);
+        call!(SystemTimeToTzSpecificLocalTimeEx(&tz, &utc, &mut local));
         system_time_to_tm(&local, tm);
 
         let local = system_time_to_file_time(&local);
         let local_sec = file_time_to_unix_seconds(&local);
 
-        let mut tz = mem::zeroed();
-        GetTimeZoneInformation(&mut tz);
-
-        // SystemTimeToTzSpecificLocalTime already applied the biases so
+        // SystemTimeToTzSpecificLocalTimeEx already applied the biases so
         // check if it non standard
         tm.tm_utcoff = (local_sec - sec) as i32;
         tm.tm_isdst = if tm.tm_utcoff == -60 * (tz.Bias + tz.StandardBias) { 0 } else { 1 };
     }
 }
 
+/// Returns the current zone's time-zone rules as they applied during `year`.
+///
+/// The overlaid standard/daylight rules carry an empty `TimeZoneKeyName`, which
+/// makes the `*Ex` conversion APIs honour these embedded rules instead of
+/// re-deriving them from the registry for the current year.
+unsafe fn dynamic_tz_for_year(year: u16) -> DYNAMIC_TIME_ZONE_INFORMATION {
+    let mut dtzi: DYNAMIC_TIME_ZONE_INFORMATION = mem::zeroed();
+    GetDynamicTimeZoneInformation(&mut dtzi);
+
+    let mut tzi: TIME_ZONE_INFORMATION = mem::zeroed();
+    if GetTimeZoneInformationForYear(year, &dtzi, &mut tzi) != 0 {
+        dtzi.Bias = tzi.Bias;
+        dtzi.StandardName = tzi.StandardName;
+        dtzi.StandardDate = tzi.StandardDate;
+        dtzi.StandardBias = tzi.StandardBias;
+        dtzi.DaylightName = tzi.DaylightName;
+        dtzi.DaylightDate = tzi.DaylightDate;
+        dtzi.DaylightBias = tzi.DaylightBias;
+        dtzi.TimeZoneKeyName = [0; 128];
+        dtzi.DynamicDaylightTimeDisabled = 0;
+    }
+    dtzi
+}
+
 fn utc_tm_to_time(tm: &Tm) -> i64 {
     unsafe {
         let mut ft = mem::zeroed();
@@ -284,7 +368,8 @@ fn local_tm_to_time(tm: &Tm) -> i64 {
         let mut ft = mem::zeroed();
         let mut utc = mem::zeroed();
         let sys_time = tm_to_system_time(tm);
-        call!(TzSpecificLocalTimeToSystemTime(ptr::null(), &sys_time, &mut utc));
+        let tz = dynamic_tz_for_year(sys_time.wYear);
+        call!(TzSpecificLocalTimeToSystemTimeEx(&tz, &sys_time, &mut utc));
         call!(SystemTimeToFileTime(&utc, &mut ft));
         file_time_to_unix_seconds(&ft)
     }